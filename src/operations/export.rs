@@ -1,7 +1,104 @@
 //! See [Mesh](crate::mesh::Mesh).
 
+use std::collections::{HashMap, HashSet};
+
 use crate::prelude::*;
 
+///
+/// A report of how many faces/vertices `Mesh::clean` removed.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CleanReport {
+    /// The number of faces removed because their three vertices were collinear or coincident.
+    pub degenerate_faces_removed: usize,
+    /// The number of vertices removed because no surviving face referenced them.
+    pub unused_vertices_removed: usize,
+}
+
+///
+/// The strategy used to compute per-vertex normals for the export buffers.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalMode {
+    /// The normal of a vertex is the average of the normals of its adjacent faces.
+    Smooth,
+    /// Each face corner gets the geometric normal of its own face, so shading is faceted
+    /// instead of interpolated across faces.
+    Flat,
+    /// The normal of a vertex is the sum of its adjacent face normals weighted by the interior
+    /// angle the face makes at that vertex, then normalized. This avoids the bias `Smooth`
+    /// introduces when several small triangles on one side of a vertex outweigh a single large
+    /// triangle on the other side.
+    AngleWeighted,
+}
+
+///
+/// A lazily-populated cache of per-vertex normals, held by the caller across calls so repeated
+/// exports of an unmodified mesh don't recompute every normal from scratch each time.
+///
+/// `Mesh` has no dirty-flag bookkeeping of its own in this tree (that would live in `mesh.rs`,
+/// which isn't part of this slice), so instead of an automatic cache that invalidates itself on
+/// every mutation, the cache is its own value: pass the same `NormalCache` to successive calls
+/// of `normals_buffer_cached`/`non_indexed_normals_buffer_cached` to reuse the computed normals,
+/// and call `invalidate()` yourself whenever the mesh's positions or topology change.
+///
+#[derive(Debug, Clone, Default)]
+pub struct NormalCache {
+    vertex_normals: Option<HashMap<VertexID, Vector3<f64>>>,
+}
+
+impl NormalCache {
+    ///
+    /// Creates an empty cache; the first call to a `*_cached` method populates it.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Marks the cache stale. Call this after changing the mesh's positions or topology; the
+    /// next call to a `*_cached` method recomputes normals from scratch.
+    ///
+    pub fn invalidate(&mut self) {
+        self.vertex_normals = None;
+    }
+
+    fn vertex_normals_or_insert_with(
+        &mut self,
+        f: impl FnOnce() -> HashMap<VertexID, Vector3<f64>>,
+    ) -> &HashMap<VertexID, Vector3<f64>> {
+        self.vertex_normals.get_or_insert_with(f)
+    }
+}
+
+///
+/// An explicit per-vertex uv-coordinate table, keyed by [VertexID].
+///
+/// `Mesh` has no uv field in this tree (that storage, and the `MeshBuilder::with_uvs` to go with
+/// it, would live in `mesh.rs`, which isn't part of this slice), so uv coordinates are carried
+/// alongside a `Mesh` as their own value instead of being read off of `self`. `Mesh::from_obj`
+/// returns one whenever the source declares a `vt` for every vertex; pass it back in to
+/// `uvs_buffer`, `non_indexed_uvs_buffer`, or `to_obj` to round-trip it.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VertexUvs(HashMap<VertexID, Vector2<f64>>);
+
+impl VertexUvs {
+    ///
+    /// Builds a uv table from an explicit per-vertex map.
+    ///
+    pub fn new(uvs: HashMap<VertexID, Vector2<f64>>) -> Self {
+        Self(uvs)
+    }
+
+    ///
+    /// Returns the uv coordinate recorded for `vertex_id`, if any.
+    ///
+    pub fn get(&self, vertex_id: VertexID) -> Option<Vector2<f64>> {
+        self.0.get(&vertex_id).copied()
+    }
+}
+
 ///
 /// # Export
 ///
@@ -13,18 +110,31 @@ impl Mesh {
     /// Use the `positions_buffer` method and `normals_buffer` method to get the positions and normals of the vertices.
     ///
     pub fn indices_buffer(&self) -> Vec<u32> {
-        let vertices: Vec<VertexID> = self.vertex_iter().collect();
+        let vertex_to_index = self.vertex_index_map();
         let mut indices = Vec::with_capacity(self.no_faces() * 3);
         for face_id in self.face_iter() {
             for halfedge_id in self.face_halfedge_iter(face_id) {
                 let vertex_id = self.walker_from_halfedge(halfedge_id).vertex_id().unwrap();
-                let index = vertices.iter().position(|v| v == &vertex_id).unwrap();
-                indices.push(index as u32);
+                indices.push(vertex_to_index[&vertex_id]);
             }
         }
         indices
     }
 
+    ///
+    /// Builds a map from each [VertexID] to its position in `vertex_iter` order, i.e. the index
+    /// it is assigned in `positions_buffer`/`normals_buffer`/`indices_buffer`.
+    ///
+    /// This is computed once and looked up in constant time per face corner, instead of
+    /// linearly scanning the vertex list for every corner of every face.
+    ///
+    fn vertex_index_map(&self) -> HashMap<VertexID, u32> {
+        self.vertex_iter()
+            .enumerate()
+            .map(|(index, vertex_id)| (vertex_id, index as u32))
+            .collect()
+    }
+
     ///
     /// Returns the positions of the vertices in an array which is meant to be used for visualisation.
     ///
@@ -51,6 +161,75 @@ impl Mesh {
             .collect::<Vec<_>>()
     }
 
+    ///
+    /// Returns the normals of the vertices in an array which is meant to be used for visualisation,
+    /// computed according to the given `mode`. See `normals_buffer` for the `Smooth` behaviour.
+    ///
+    /// **Note:** `NormalMode::Flat` has no single well-defined normal for a vertex shared between
+    /// several faces, so in the indexed buffer it resolves to the normal of the first adjacent
+    /// face reached while walking the connectivity. Use `non_indexed_normals_buffer_with` to get
+    /// a true per-corner flat-shaded buffer, since there every face corner owns its own entry.
+    ///
+    pub fn normals_buffer_with(&self, mode: NormalMode) -> Vec<Vector3<f64>> {
+        self.vertex_iter()
+            .map(|vertex_id| self.vertex_normal_with(vertex_id, mode))
+            .collect::<Vec<_>>()
+    }
+
+    fn vertex_normal_with(&self, vertex_id: VertexID, mode: NormalMode) -> Vector3<f64> {
+        match mode {
+            NormalMode::Smooth => self.vertex_normal(vertex_id),
+            NormalMode::Flat => self
+                .vertex_adjacent_faces(vertex_id)
+                .first()
+                .map(|face_id| self.face_normal(*face_id))
+                .unwrap_or_else(|| vec3(0.0, 0.0, 0.0)),
+            NormalMode::AngleWeighted => self.angle_weighted_vertex_normal(vertex_id),
+        }
+    }
+
+    ///
+    /// Computes the normal of a vertex as the sum of its adjacent face normals weighted by the
+    /// interior angle the face makes at that vertex (`acos` of the normalized edge vectors
+    /// leaving the vertex), then normalized. See `NormalMode::AngleWeighted`.
+    ///
+    fn angle_weighted_vertex_normal(&self, vertex_id: VertexID) -> Vector3<f64> {
+        let position = self.vertex_position(vertex_id);
+        let mut sum = vec3(0.0, 0.0, 0.0);
+        let mut has_adjacent_face = false;
+        for face_id in self.vertex_adjacent_faces(vertex_id) {
+            has_adjacent_face = true;
+            let (v0, v1, v2) = self.face_vertices(face_id);
+            let (other0, other1) = if vertex_id == v0 {
+                (v1, v2)
+            } else if vertex_id == v1 {
+                (v2, v0)
+            } else {
+                (v0, v1)
+            };
+            let e0 = (self.vertex_position(other0) - position).normalize();
+            let e1 = (self.vertex_position(other1) - position).normalize();
+            let theta = e0.dot(e1).clamp(-1.0, 1.0).acos();
+            sum += theta * self.face_normal(face_id);
+        }
+        // An orphan vertex with no adjacent faces (e.g. left over from import, or any mesh not
+        // yet run through `clean()`) has no well-defined normal; fall back to zero instead of
+        // normalizing a zero vector into NaN, matching the `Flat` fallback above.
+        if !has_adjacent_face {
+            return vec3(0.0, 0.0, 0.0);
+        }
+        sum.normalize()
+    }
+
+    ///
+    /// Returns the faces adjacent to a vertex by walking the halfedges leaving it.
+    ///
+    fn vertex_adjacent_faces(&self, vertex_id: VertexID) -> Vec<FaceID> {
+        self.vertex_halfedge_iter(vertex_id)
+            .filter_map(|halfedge_id| self.walker_from_halfedge(halfedge_id).face_id())
+            .collect()
+    }
+
     ///
     /// Returns the positions of the face corners in an array which is meant to be used for visualisation.
     ///
@@ -70,26 +249,537 @@ impl Mesh {
     ///
     /// **Note:** The normal of a vertex is computed as the average of the normals of the adjacent faces.
     ///
-    /// **Note:** The normals are computed from the connectivity and positions each time this method is invoked.
+    /// **Note:** The normals are computed from the connectivity and positions each time this method
+    /// is invoked. A vertex is usually shared by several face corners, so this builds a per-vertex
+    /// normal map once up front and looks each vertex normal up at most once per corner, instead of
+    /// recomputing it from scratch for every corner that references it. The map itself is not kept
+    /// around between calls.
     ///
     pub fn non_indexed_normals_buffer(&self) -> Vec<f64> {
+        let vertex_normals = self.vertex_normal_cache();
+        let mut normals = Vec::with_capacity(self.no_faces() * 3 * 3);
+        for face_id in self.face_iter() {
+            let (v0, v1, v2) = self.face_vertices(face_id);
+            push_vec3(&mut normals, vertex_normals[&v0]);
+            push_vec3(&mut normals, vertex_normals[&v1]);
+            push_vec3(&mut normals, vertex_normals[&v2]);
+        }
+        normals
+    }
+
+    ///
+    /// Builds a map from each [VertexID] to its normal, computed once up front so that a buffer
+    /// build looks each vertex normal up at most once instead of once per incident face corner.
+    ///
+    fn vertex_normal_cache(&self) -> HashMap<VertexID, Vector3<f64>> {
+        self.vertex_iter()
+            .map(|vertex_id| (vertex_id, self.vertex_normal(vertex_id)))
+            .collect()
+    }
+
+    ///
+    /// Same as `normals_buffer`, but populates `cache` on first use and reuses it on every
+    /// subsequent call instead of recomputing. Call `cache.invalidate()` after changing the
+    /// mesh's positions or topology; see [NormalCache].
+    ///
+    pub fn normals_buffer_cached(&self, cache: &mut NormalCache) -> Vec<Vector3<f64>> {
+        let vertex_normals = cache.vertex_normals_or_insert_with(|| self.vertex_normal_cache());
+        self.vertex_iter()
+            .map(|vertex_id| vertex_normals[&vertex_id])
+            .collect()
+    }
+
+    ///
+    /// Same as `non_indexed_normals_buffer`, but populates `cache` on first use and reuses it on
+    /// every subsequent call instead of recomputing. Call `cache.invalidate()` after changing the
+    /// mesh's positions or topology; see [NormalCache].
+    ///
+    pub fn non_indexed_normals_buffer_cached(&self, cache: &mut NormalCache) -> Vec<f64> {
+        let vertex_normals = cache.vertex_normals_or_insert_with(|| self.vertex_normal_cache());
         let mut normals = Vec::with_capacity(self.no_faces() * 3 * 3);
         for face_id in self.face_iter() {
             let (v0, v1, v2) = self.face_vertices(face_id);
-            push_vec3(&mut normals, self.vertex_normal(v0));
-            push_vec3(&mut normals, self.vertex_normal(v1));
-            push_vec3(&mut normals, self.vertex_normal(v2));
+            push_vec3(&mut normals, vertex_normals[&v0]);
+            push_vec3(&mut normals, vertex_normals[&v1]);
+            push_vec3(&mut normals, vertex_normals[&v2]);
         }
         normals
     }
+
+    ///
+    /// Returns the normals of the face corners in an array which is meant to be used for visualisation,
+    /// computed according to the given `mode`.
+    ///
+    /// **Note:** `NormalMode::Flat` gives every corner of a face the same geometric face normal,
+    /// so shading is faceted at edges instead of interpolated.
+    ///
+    pub fn non_indexed_normals_buffer_with(&self, mode: NormalMode) -> Vec<f64> {
+        let vertex_normals = match mode {
+            NormalMode::Flat => None,
+            NormalMode::Smooth | NormalMode::AngleWeighted => Some(
+                self.vertex_iter()
+                    .map(|vertex_id| (vertex_id, self.vertex_normal_with(vertex_id, mode)))
+                    .collect::<HashMap<_, _>>(),
+            ),
+        };
+        let mut normals = Vec::with_capacity(self.no_faces() * 3 * 3);
+        for face_id in self.face_iter() {
+            match &vertex_normals {
+                None => {
+                    let face_normal = self.face_normal(face_id);
+                    push_vec3(&mut normals, face_normal);
+                    push_vec3(&mut normals, face_normal);
+                    push_vec3(&mut normals, face_normal);
+                }
+                Some(vertex_normals) => {
+                    let (v0, v1, v2) = self.face_vertices(face_id);
+                    push_vec3(&mut normals, vertex_normals[&v0]);
+                    push_vec3(&mut normals, vertex_normals[&v1]);
+                    push_vec3(&mut normals, vertex_normals[&v2]);
+                }
+            }
+        }
+        normals
+    }
+
+    ///
+    /// Returns the uv coordinates of the vertices in an array which is meant to be used for
+    /// visualisation, or `None` if `uvs` does not carry a coordinate for every vertex.
+    ///
+    /// **Note:** The connectivity of the vertices are attained by the `indices_buffer` method.
+    ///
+    /// **Note:** `Mesh` itself has no uv field in this tree (that would live in `mesh.rs`, which
+    /// isn't part of this slice), so uv coordinates are threaded through as an explicit
+    /// [VertexUvs] table rather than read off of `self`. See [VertexUvs] for the reasoning.
+    ///
+    pub fn uvs_buffer(&self, uvs: &VertexUvs) -> Option<Vec<Vector2<f64>>> {
+        self.vertex_iter()
+            .map(|vertex_id| uvs.get(vertex_id))
+            .collect()
+    }
+
+    ///
+    /// Returns the uv coordinates of the face corners in an array which is meant to be used for
+    /// visualisation, or `None` if `uvs` does not carry a coordinate for every vertex.
+    ///
+    pub fn non_indexed_uvs_buffer(&self, uvs: &VertexUvs) -> Option<Vec<f64>> {
+        let mut result = Vec::with_capacity(self.no_faces() * 3 * 2);
+        for face_id in self.face_iter() {
+            let (v0, v1, v2) = self.face_vertices(face_id);
+            for vertex_id in [v0, v1, v2] {
+                let uv = uvs.get(vertex_id)?;
+                result.push(uv.x);
+                result.push(uv.y);
+            }
+        }
+        Some(result)
+    }
+
+    ///
+    /// Serializes the mesh to the Wavefront OBJ format, writing `v`, `vn` and, if `uvs` carries a
+    /// coordinate for every vertex, `vt` records, followed by one 1-based triangle face record
+    /// per face.
+    ///
+    /// Reuses the same vertex-to-index connectivity walk as `indices_buffer` so the emitted face
+    /// records line up with the `v`/`vn`/`vt` records.
+    ///
+    pub fn to_obj(&self, uvs: Option<&VertexUvs>) -> String {
+        let positions = self.positions_buffer();
+        let normals = self.normals_buffer();
+        let uvs = uvs.and_then(|uvs| self.uvs_buffer(uvs));
+        let indices = self.indices_buffer();
+
+        let mut obj = String::new();
+        for p in &positions {
+            obj.push_str(&format!("v {} {} {}\n", p.x, p.y, p.z));
+        }
+        if let Some(uvs) = &uvs {
+            for uv in uvs {
+                obj.push_str(&format!("vt {} {}\n", uv.x, uv.y));
+            }
+        }
+        for n in &normals {
+            obj.push_str(&format!("vn {} {} {}\n", n.x, n.y, n.z));
+        }
+        for face in indices.chunks(3) {
+            if uvs.is_some() {
+                obj.push_str(&format!(
+                    "f {0}/{0}/{0} {1}/{1}/{1} {2}/{2}/{2}\n",
+                    face[0] + 1,
+                    face[1] + 1,
+                    face[2] + 1
+                ));
+            } else {
+                obj.push_str(&format!(
+                    "f {0}//{0} {1}//{1} {2}//{2}\n",
+                    face[0] + 1,
+                    face[1] + 1,
+                    face[2] + 1
+                ));
+            }
+        }
+        obj
+    }
+
+    ///
+    /// Removes faces whose three vertices are collinear or coincident (the cross product of
+    /// their edges has magnitude below `epsilon`), then removes any vertex no surviving face
+    /// references, so the export buffers contain no zero-area triangles or orphan vertices that
+    /// would otherwise break downstream rendering and normal averaging.
+    ///
+    pub fn clean(&mut self, epsilon: f64) -> CleanReport {
+        // Removing a face can already drop vertices it leaves without any face, so count the
+        // vertices removed over the whole pass rather than only those `remove_unused_vertices`
+        // finds afterwards.
+        let no_vertices_before = self.no_vertices();
+        let degenerate_faces_removed = self.remove_degenerate_faces(epsilon);
+        self.remove_unused_vertices();
+        let unused_vertices_removed = no_vertices_before - self.no_vertices();
+        CleanReport {
+            degenerate_faces_removed,
+            unused_vertices_removed,
+        }
+    }
+
+    ///
+    /// Removes faces whose three vertices are collinear or coincident, i.e. the cross product of
+    /// their edges has magnitude below `epsilon`. Returns the number of faces removed.
+    ///
+    fn remove_degenerate_faces(&mut self, epsilon: f64) -> usize {
+        let degenerate: Vec<FaceID> = self
+            .face_iter()
+            .filter(|&face_id| {
+                let (p0, p1, p2) = self.face_positions(face_id);
+                (p1 - p0).cross(p2 - p0).magnitude() < epsilon
+            })
+            .collect();
+        let count = degenerate.len();
+        for face_id in degenerate {
+            self.remove_face(face_id);
+        }
+        count
+    }
+
+    ///
+    /// Removes every vertex no face references anymore, compacting the vertex set so
+    /// `positions_buffer`/`indices_buffer` contain no orphan vertex. Returns the number of
+    /// vertices removed.
+    ///
+    fn remove_unused_vertices(&mut self) -> usize {
+        let referenced: HashSet<VertexID> = self
+            .face_iter()
+            .flat_map(|face_id| {
+                let (v0, v1, v2) = self.face_vertices(face_id);
+                [v0, v1, v2]
+            })
+            .collect();
+        let unused: Vec<VertexID> = self
+            .vertex_iter()
+            .filter(|vertex_id| !referenced.contains(vertex_id))
+            .collect();
+        let count = unused.len();
+        for vertex_id in unused {
+            self.remove_vertex(vertex_id);
+        }
+        count
+    }
+
+    ///
+    /// Parses a Wavefront OBJ source string into a `Mesh`.
+    ///
+    /// Accepts `f` records in the `v`, `v//vn`, `v/vt`, and `v/vt/vn` corner formats (only the
+    /// vertex component is used to build connectivity; normals are recomputed by the mesh
+    /// itself). Faces with more than three corners are fan-triangulated around their first
+    /// corner, since this crate only represents triangle meshes. Face indices may be negative,
+    /// in which case they are relative to the most recently declared vertex, as in the `tobj`
+    /// parser.
+    ///
+    /// Returns the parsed uvs as a [VertexUvs] alongside the mesh when the source declares a
+    /// `vt` for every vertex; otherwise the second element is `None`.
+    ///
+    pub fn from_obj(source: &str) -> Result<(Mesh, Option<VertexUvs>), ObjError> {
+        let mut positions: Vec<f64> = Vec::new();
+        let mut texcoords: Vec<(f64, f64)> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+        let mut vertex_uvs: HashMap<u32, (f64, f64)> = HashMap::new();
+
+        for (line_index, line) in source.lines().enumerate() {
+            let line_no = line_index + 1;
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("v") => {
+                    let coords = tokens
+                        .take(3)
+                        .map(|t| {
+                            t.parse::<f64>()
+                                .map_err(|_| ObjError::ParseFloat { line: line_no })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if coords.len() < 3 {
+                        return Err(ObjError::MalformedVertex { line: line_no });
+                    }
+                    positions.extend_from_slice(&coords);
+                }
+                Some("vt") => {
+                    let coords = tokens
+                        .take(2)
+                        .map(|t| {
+                            t.parse::<f64>()
+                                .map_err(|_| ObjError::ParseFloat { line: line_no })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if coords.len() < 2 {
+                        return Err(ObjError::MalformedVertex { line: line_no });
+                    }
+                    texcoords.push((coords[0], coords[1]));
+                }
+                Some("f") => {
+                    let no_vertices = (positions.len() / 3) as i64;
+                    let no_texcoords = texcoords.len() as i64;
+                    let corners = tokens
+                        .map(|token| {
+                            parse_face_corner(token, no_vertices, no_texcoords)
+                                .ok_or(ObjError::MalformedFace { line: line_no })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if corners.len() < 3 {
+                        return Err(ObjError::DegenerateFace { line: line_no });
+                    }
+                    for &(vertex_index, uv_index) in &corners {
+                        if let Some(uv_index) = uv_index {
+                            vertex_uvs
+                                .entry(vertex_index)
+                                .or_insert_with(|| texcoords[uv_index as usize]);
+                        }
+                    }
+                    // Fan triangulation of the n-gon around its first corner.
+                    for i in 1..corners.len() - 1 {
+                        indices.push(corners[0].0);
+                        indices.push(corners[i].0);
+                        indices.push(corners[i + 1].0);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let no_vertices = positions.len() / 3;
+        let mesh = MeshBuilder::new()
+            .with_positions(positions)
+            .with_indices(indices)
+            .build()
+            .map_err(ObjError::Build)?;
+
+        let uvs = if !texcoords.is_empty() && vertex_uvs.len() == no_vertices {
+            // `vertex_iter` walks vertices in the same order `MeshBuilder` assigned them, the
+            // same assumption `vertex_index_map` makes to line up `positions_buffer` with
+            // `indices_buffer`, so the i-th entry here is vertex `i` from the positions array.
+            let vertex_ids: Vec<VertexID> = mesh.vertex_iter().collect();
+            let table = (0..no_vertices as u32)
+                .map(|vertex_index| {
+                    let (u, v) = vertex_uvs[&vertex_index];
+                    (vertex_ids[vertex_index as usize], vec2(u, v))
+                })
+                .collect();
+            Some(VertexUvs::new(table))
+        } else {
+            None
+        };
+
+        Ok((mesh, uvs))
+    }
+}
+
+///
+/// Parses an OBJ face-corner token in the `v`, `v//vn`, `v/vt` or `v/vt/vn` format into a
+/// 0-based `(vertex_index, uv_index)` pair. Negative/relative indices are resolved against the
+/// count of vertices/texture-coordinates already declared, as in the `tobj` parser (an index of
+/// `-1` refers to the most recently declared entry).
+///
+fn parse_face_corner(
+    token: &str,
+    no_vertices: i64,
+    no_texcoords: i64,
+) -> Option<(u32, Option<u32>)> {
+    let mut parts = token.split('/');
+    let vertex_index = resolve_obj_index(parts.next()?, no_vertices)?;
+    let uv_index = match parts.next() {
+        Some("") | None => None,
+        Some(part) => Some(resolve_obj_index(part, no_texcoords)?),
+    };
+    Some((vertex_index, uv_index))
+}
+
+///
+/// Resolves a single OBJ index component (1-based, or negative/relative to the most recently
+/// declared entry) into a 0-based index.
+///
+fn resolve_obj_index(part: &str, count: i64) -> Option<u32> {
+    let raw: i64 = part.parse().ok()?;
+    let index = if raw < 0 { count + raw } else { raw - 1 };
+    if index < 0 || index >= count {
+        None
+    } else {
+        Some(index as u32)
+    }
 }
 
+///
+/// An error returned while importing an OBJ file with `Mesh::from_obj`.
+///
+#[derive(Debug)]
+pub enum ObjError {
+    /// A `v` record had fewer than three components, or a `vt` record fewer than two.
+    MalformedVertex { line: usize },
+    /// A numeric component of a record could not be parsed as a float.
+    ParseFloat { line: usize },
+    /// A face record referenced a corner that could not be parsed or does not exist.
+    MalformedFace { line: usize },
+    /// A face record had fewer than three corners.
+    DegenerateFace { line: usize },
+    /// Building the mesh from the parsed positions and indices failed.
+    Build(crate::mesh_builder::Error),
+}
+
+impl std::fmt::Display for ObjError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjError::MalformedVertex { line } => {
+                write!(f, "malformed 'v' or 'vt' record on line {}", line)
+            }
+            ObjError::ParseFloat { line } => write!(f, "could not parse a number on line {}", line),
+            ObjError::MalformedFace { line } => write!(f, "malformed 'f' record on line {}", line),
+            ObjError::DegenerateFace { line } => {
+                write!(f, "degenerate face (fewer than 3 corners) on line {}", line)
+            }
+            ObjError::Build(error) => write!(f, "failed to build mesh: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ObjError {}
+
 fn push_vec3(vec: &mut Vec<f64>, vec3: Vec3) {
     for i in 0..3 {
         vec.push(vec3[i]);
     }
 }
 
+///
+/// The factor position, normal and uv components are scaled by before being rounded to integers
+/// (i.e. rounded to 5 decimal places) and used as a hash key when welding coincident face corners
+/// in `weld_non_indexed_buffers`/`weld_non_indexed_buffers_with_uvs`.
+///
+const WELD_QUANTIZATION_FACTOR: f64 = 1.0e5;
+
+fn quantize(v: Vec3) -> (i64, i64, i64) {
+    (
+        (v.x * WELD_QUANTIZATION_FACTOR).round() as i64,
+        (v.y * WELD_QUANTIZATION_FACTOR).round() as i64,
+        (v.z * WELD_QUANTIZATION_FACTOR).round() as i64,
+    )
+}
+
+fn quantize2(v: Vector2<f64>) -> (i64, i64) {
+    (
+        (v.x * WELD_QUANTIZATION_FACTOR).round() as i64,
+        (v.y * WELD_QUANTIZATION_FACTOR).round() as i64,
+    )
+}
+
+///
+/// Collapses a non-indexed face-corner buffer, as produced by `non_indexed_positions_buffer`
+/// and `non_indexed_normals_buffer`, into a compact indexed buffer.
+///
+/// Face corners whose position and normal both quantize to the same key (see
+/// `WELD_QUANTIZATION_FACTOR`) are merged into a single vertex, the same hash-based
+/// deduplication approach used by `bevy_obj`'s mesh loader. Returns `(indices, positions,
+/// normals)` such that `positions[indices[i]]` is the position of face corner `i` and likewise
+/// for `normals`.
+///
+/// **Note:** This does not take uvs into account, so two corners that share a position and
+/// normal but sit on opposite sides of a uv seam will incorrectly be merged into one vertex. Use
+/// `weld_non_indexed_buffers_with_uvs` for a mesh that carries uvs.
+///
+/// # Panics
+///
+/// Panics if `positions` and `normals` do not describe the same number of face corners.
+///
+pub fn weld_non_indexed_buffers(
+    positions: &[f64],
+    normals: &[f64],
+) -> (Vec<u32>, Vec<Vec3>, Vec<Vec3>) {
+    let (indices, welded_positions, welded_normals, _) =
+        weld_non_indexed_buffers_with_uvs(positions, normals, None);
+    (indices, welded_positions, welded_normals)
+}
+
+///
+/// Collapses a non-indexed face-corner buffer into a compact indexed buffer, the same as
+/// `weld_non_indexed_buffers`, but also takes the optional uv buffer produced by
+/// `non_indexed_uvs_buffer` into account: when `uvs` is `Some`, two corners are only merged if
+/// their positions, normals, *and* uvs all quantize to the same key (see
+/// `WELD_QUANTIZATION_FACTOR`). This keeps corners on opposite sides of a texture seam distinct,
+/// since they share a position and normal but not a uv.
+///
+/// Returns `(indices, positions, normals, uvs)`, where `uvs` is `Some` iff the input `uvs` was.
+///
+/// # Panics
+///
+/// Panics if `positions`, `normals`, and (when given) `uvs` do not describe the same number of
+/// face corners.
+///
+pub fn weld_non_indexed_buffers_with_uvs(
+    positions: &[f64],
+    normals: &[f64],
+    uvs: Option<&[f64]>,
+) -> (Vec<u32>, Vec<Vec3>, Vec<Vec3>, Option<Vec<Vector2<f64>>>) {
+    assert_eq!(
+        positions.len(),
+        normals.len(),
+        "positions and normals buffers must describe the same number of face corners"
+    );
+    let no_corners = positions.len() / 3;
+    if let Some(uvs) = uvs {
+        assert_eq!(
+            no_corners,
+            uvs.len() / 2,
+            "uvs buffer must describe the same number of face corners as positions/normals"
+        );
+    }
+    let mut indices = Vec::with_capacity(no_corners);
+    let mut welded_positions = Vec::new();
+    let mut welded_normals = Vec::new();
+    let mut welded_uvs = uvs.map(|_| Vec::new());
+    let mut lookup: HashMap<((i64, i64, i64), (i64, i64, i64), Option<(i64, i64)>), u32> =
+        HashMap::new();
+
+    for corner in 0..no_corners {
+        let position = vec3(
+            positions[3 * corner],
+            positions[3 * corner + 1],
+            positions[3 * corner + 2],
+        );
+        let normal = vec3(
+            normals[3 * corner],
+            normals[3 * corner + 1],
+            normals[3 * corner + 2],
+        );
+        let uv = uvs.map(|uvs| vec2(uvs[2 * corner], uvs[2 * corner + 1]));
+        let key = (quantize(position), quantize(normal), uv.map(quantize2));
+        let index = *lookup.entry(key).or_insert_with(|| {
+            welded_positions.push(position);
+            welded_normals.push(normal);
+            if let (Some(welded_uvs), Some(uv)) = (&mut welded_uvs, uv) {
+                welded_uvs.push(uv);
+            }
+            (welded_positions.len() - 1) as u32
+        });
+        indices.push(index);
+    }
+    (indices, welded_positions, welded_normals, welded_uvs)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -212,4 +902,187 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_weld_non_indexed_buffers() {
+        let mesh: Mesh = RawMesh::cylinder(16).into();
+        let positions = mesh.non_indexed_positions_buffer();
+        let normals = mesh.non_indexed_normals_buffer();
+
+        let (indices, welded_positions, welded_normals) =
+            weld_non_indexed_buffers(&positions, &normals);
+
+        assert_eq!(indices.len(), mesh.no_faces() * 3);
+        assert_eq!(welded_positions.len(), mesh.no_vertices());
+        assert_eq!(welded_normals.len(), mesh.no_vertices());
+
+        for corner in 0..indices.len() {
+            let welded = welded_positions[indices[corner] as usize];
+            let original = vec3(
+                positions[3 * corner],
+                positions[3 * corner + 1],
+                positions[3 * corner + 2],
+            );
+            assert_eq!(welded, original);
+        }
+    }
+
+    #[test]
+    fn test_weld_with_uvs_keeps_seam_corners_distinct() {
+        // Two triangles sharing a position and normal but disagreeing on uv, as happens across
+        // a texture seam: welding by position/normal alone would incorrectly merge them.
+        let positions = vec![
+            0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0,
+            0.0,
+        ];
+        let normals = vec![
+            0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0,
+            1.0,
+        ];
+        let uvs = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5, 0.0, 1.0, 0.0, 0.0, 1.0];
+
+        let (indices, welded_positions, _, welded_uvs) =
+            weld_non_indexed_buffers_with_uvs(&positions, &normals, Some(&uvs));
+
+        // The first corner of each triangle shares a position/normal but not a uv, so it must
+        // remain two distinct welded vertices even though position-only welding would merge them.
+        assert_ne!(indices[0], indices[3]);
+        assert_eq!(welded_positions.len(), 4);
+        assert_eq!(welded_uvs.unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_flat_normals_match_face_normal() {
+        let mesh: Mesh = RawMesh::cylinder(16).into();
+        let normals = mesh.non_indexed_normals_buffer_with(NormalMode::Flat);
+
+        for (face, face_id) in mesh.face_iter().enumerate() {
+            let face_normal = mesh.face_normal(face_id);
+            for corner in 0..3 {
+                let offset = 9 * face + 3 * corner;
+                let normal = vec3(normals[offset], normals[offset + 1], normals[offset + 2]);
+                assert_eq!(normal, face_normal);
+            }
+        }
+    }
+
+    #[test]
+    fn test_angle_weighted_normals_are_unit_length() {
+        let mesh: Mesh = RawMesh::cylinder(16).into();
+        let normals = mesh.normals_buffer_with(NormalMode::AngleWeighted);
+
+        assert_eq!(normals.len(), mesh.no_vertices());
+        for normal in normals {
+            assert!((normal.magnitude() - 1.0).abs() < 0.00001);
+        }
+    }
+
+    #[test]
+    fn test_normals_buffer_cached_matches_uncached_and_reuses_until_invalidated() {
+        let mesh: Mesh = RawMesh::cylinder(16).into();
+        let mut cache = NormalCache::new();
+
+        let cached = mesh.normals_buffer_cached(&mut cache);
+        assert_eq!(cached, mesh.normals_buffer());
+
+        // A second call with the same cache must return the same values without the caller
+        // having to recompute anything, and must keep doing so until `invalidate()` is called.
+        assert_eq!(mesh.normals_buffer_cached(&mut cache), cached);
+
+        cache.invalidate();
+        assert_eq!(mesh.normals_buffer_cached(&mut cache), cached);
+
+        let non_indexed_cached = mesh.non_indexed_normals_buffer_cached(&mut cache);
+        assert_eq!(non_indexed_cached, mesh.non_indexed_normals_buffer());
+    }
+
+    #[test]
+    fn test_obj_round_trip() {
+        let mesh: Mesh = RawMesh::cylinder(16).into();
+        let obj = mesh.to_obj(None);
+        let (parsed, uvs) = Mesh::from_obj(&obj).unwrap();
+
+        assert_eq!(parsed.no_vertices(), mesh.no_vertices());
+        assert_eq!(parsed.no_faces(), mesh.no_faces());
+        assert!(uvs.is_none());
+    }
+
+    #[test]
+    fn test_obj_triangulates_polygon_faces() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n";
+        let (mesh, _) = Mesh::from_obj(obj).unwrap();
+
+        assert_eq!(mesh.no_vertices(), 4);
+        assert_eq!(mesh.no_faces(), 2);
+    }
+
+    #[test]
+    fn test_obj_negative_face_indices() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 1 1 0\nf -3 -2 -1\n";
+        let (mesh, _) = Mesh::from_obj(obj).unwrap();
+
+        assert_eq!(mesh.no_vertices(), 3);
+        assert_eq!(mesh.no_faces(), 1);
+    }
+
+    #[test]
+    fn test_obj_rejects_degenerate_face() {
+        let obj = "v 0 0 0\nv 1 0 0\nf 1 2\n";
+        assert!(matches!(
+            Mesh::from_obj(obj),
+            Err(ObjError::DegenerateFace { line: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_obj_rejects_out_of_range_uv_index() {
+        // Only 3 `vt` records are declared, so `99` is out of range and must be a clean error
+        // instead of an unchecked `texcoords[98]` panic.
+        let obj = "v 0 0 0\nv 1 0 0\nv 1 1 0\nvt 0 0\nvt 1 0\nvt 1 1\nf 1/99 2/1 3/1\n";
+        assert!(matches!(
+            Mesh::from_obj(obj),
+            Err(ObjError::MalformedFace { line: 7 })
+        ));
+    }
+
+    #[test]
+    fn test_uvs_buffer_absent_by_default() {
+        let mesh: Mesh = RawMesh::cylinder(16).into();
+        let uvs = VertexUvs::default();
+        assert!(mesh.uvs_buffer(&uvs).is_none());
+        assert!(mesh.non_indexed_uvs_buffer(&uvs).is_none());
+    }
+
+    #[test]
+    fn test_obj_with_uvs_round_trip() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 1 1 0\nvt 0 0\nvt 1 0\nvt 1 1\nf 1/1 2/2 3/3\n";
+        let (mesh, uvs) = Mesh::from_obj(obj).unwrap();
+        let uvs = uvs.unwrap();
+
+        let buffer = mesh.uvs_buffer(&uvs).unwrap();
+        assert_eq!(buffer, vec![vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(1.0, 1.0)]);
+
+        let exported = mesh.to_obj(Some(&uvs));
+        assert!(exported.contains("vt "));
+    }
+
+    #[test]
+    fn test_clean_removes_degenerate_faces_and_orphan_vertices() {
+        // A valid triangle (1, 2, 3), a fourth, unreferenced orphan vertex (4), and a second
+        // face (2, 5, 6) whose three vertices all sit on the x-axis and are therefore
+        // collinear/zero-area rather than sharing a single repeated index, so the fixture
+        // unambiguously exercises the epsilon-based degenerate check instead of leaning on
+        // undefined `MeshBuilder` behaviour for a vertex-to-itself face.
+        let obj = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 5 5 5\nv 2 0 0\nv 4 0 0\nf 1 2 3\nf 2 5 6\n";
+        let (mut mesh, _) = Mesh::from_obj(obj).unwrap();
+        let no_faces_before = mesh.no_faces();
+        let no_vertices_before = mesh.no_vertices();
+
+        let report = mesh.clean(0.00001);
+
+        assert_eq!(report.degenerate_faces_removed, no_faces_before - 1);
+        assert_eq!(report.unused_vertices_removed, no_vertices_before - 3);
+        assert_eq!(mesh.no_faces(), 1);
+        assert_eq!(mesh.no_vertices(), 3);
+    }
 }